@@ -35,30 +35,79 @@
 //! ```
 //!
 
-//! 
+//!
 use std::cell::Cell;
 use std::time::{Duration, Instant};
 
+/// Determines what happens when a `Timer` is polled past its `duration`.
+///
+/// A `Once` timer represents a single timeout, while a `Repeating` timer
+/// represents a fixed-period tick that keeps firing as long as it is polled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerMode {
+    /// The timer expires a single time; `poll()` saturates at 1.
+    Once,
+    /// The timer fires once per `duration` and keeps going.
+    Repeating,
+}
+
+/// How a [`wait_timeout`](Timer::wait_timeout) call ended.
+///
+/// Mirrors crosvm's `WaitResult`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitResult {
+    /// The timer expired before the bound was reached.
+    Expired,
+    /// The bound `max` elapsed before the timer expired.
+    Timeout,
+}
+
 /// Timer provides extremely basic timing abilities
 #[derive(Debug, Clone)]
 pub struct Timer {
     instant: Cell<Instant>,
     duration: Duration,
+    mode: TimerMode,
+    driven: bool,
+    ticked: Cell<Duration>,
+    just_finished: Cell<bool>,
+    accumulated: Cell<Duration>,
+    paused_at: Cell<Option<Instant>>,
 }
 
 
 impl Timer {
     /// Creates a new timer of zero `Duration`.
-    /// 
-    /// Similar to `std::time::Instant` as this is really only useful 
+    ///
+    /// Similar to `std::time::Instant` as this is really only useful
     /// for getting `elapsed` time since `reset`
     pub fn new() -> Timer {
         Timer {
             instant: Cell::new(Instant::now()),
             duration: Duration::from_secs(0),
+            mode: TimerMode::Once,
+            driven: false,
+            ticked: Cell::new(Duration::from_secs(0)),
+            just_finished: Cell::new(false),
+            accumulated: Cell::new(Duration::from_secs(0)),
+            paused_at: Cell::new(None),
         }
     }
 
+    /// Creates a new externally-driven timer of `duration` length.
+    ///
+    /// A driven timer ignores the wall clock entirely: instead of consulting
+    /// `Instant::now()`, it accumulates time fed to it through
+    /// [`tick`](Timer::tick). This makes timers fully deterministic — handy for
+    /// callers that already track their own frame delta, and for writing tests
+    /// without `std::thread::sleep`.
+    pub fn driven(duration: Duration) -> Timer {
+        let mut timer = Timer::new();
+        timer.duration = duration;
+        timer.driven = true;
+        timer
+    }
+
     /// Creates a new timer with `duration` length
     pub fn with_duration(duration: Duration) -> Timer {
         let mut timer = Timer::new();
@@ -66,6 +115,37 @@ impl Timer {
         timer
     }
 
+    /// Creates a new repeating timer that fires once per `interval`.
+    ///
+    /// Unlike `with_duration`, a timer built this way is meant to be polled
+    /// with [`poll`](Timer::poll): each call reports how many full intervals
+    /// have elapsed and advances the timer forward by that many intervals so a
+    /// periodic control loop doesn't drift even when it runs slower than the
+    /// tick rate.
+    pub fn with_interval(interval: Duration) -> Timer {
+        let mut timer = Timer::new();
+        timer.duration = interval;
+        timer.mode = TimerMode::Repeating;
+        timer
+    }
+
+    /// Creates a new timer that expires at an absolute `deadline`.
+    ///
+    /// Following smol's `Timer::at`, `expired()` becomes true once
+    /// `Instant::now() >= deadline`. Internally the deadline is stored as a
+    /// base `instant` of now and a `duration` of the time until `deadline`
+    /// (saturating to zero for a deadline already in the past), so `elapsed()`,
+    /// `wait()`, and `remaining()` stay consistent with the rest of the API.
+    /// Handy when code already has an absolute deadline rather than a relative
+    /// timeout.
+    pub fn at(deadline: Instant) -> Timer {
+        let now = Instant::now();
+        let mut timer = Timer::new();
+        timer.instant.set(now);
+        timer.duration = deadline.saturating_duration_since(now);
+        timer
+    }
+
     /// Resets the timer.
     /// 
     /// # Note
@@ -76,13 +156,132 @@ impl Timer {
     /// `elapsed()` will start over at 0 after a `reset()`
     pub fn reset(&self) {
         self.instant.set(Instant::now());
+        self.ticked.set(Duration::from_secs(0));
+        self.just_finished.set(false);
+        self.accumulated.set(Duration::from_secs(0));
+        self.paused_at.set(None);
+    }
+
+    /// Pause the timer, freezing `elapsed()`/`expired()` at their current value.
+    ///
+    /// Modeled on Bevy's paused timers. The time banked so far is remembered so
+    /// that [`resume`](Timer::resume) continues from where it left off. Calling
+    /// `pause` on an already-paused timer is a no-op. Like `reset`, this only
+    /// needs `&self`.
+    pub fn pause(&self) {
+        if self.paused_at.get().is_none() {
+            self.accumulated
+                .set(self.accumulated.get() + self.running_span());
+            self.paused_at.set(Some(Instant::now()));
+        }
+    }
+
+    /// Resume a paused timer, continuing from the frozen `elapsed()` value.
+    ///
+    /// Calling `resume` on a timer that isn't paused is a no-op.
+    pub fn resume(&self) {
+        if self.paused_at.get().is_some() {
+            self.instant.set(Instant::now());
+            self.paused_at.set(None);
+        }
+    }
+
+    /// Returns `true` while the timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.get().is_some()
+    }
+
+    /// Time counted by the current running span, i.e. since the last
+    /// `reset`/`resume`. Zero for a driven timer (which banks time through
+    /// `tick` instead) or while paused.
+    fn running_span(&self) -> Duration {
+        if self.driven || self.paused_at.get().is_some() {
+            Duration::from_secs(0)
+        } else {
+            self.instant.get().elapsed()
+        }
     }
 
     /// Check if the timer is expired
-    /// 
+    ///
     /// `expired` = `elapsed` >= `duration`
     pub fn expired(&self) -> bool {
-        self.instant.get().elapsed() >= self.duration
+        self.elapsed() >= self.duration
+    }
+
+    /// Feed `delta` elapsed time into an externally-driven timer.
+    ///
+    /// Only meaningful for timers created with [`driven`](Timer::driven): the
+    /// accumulated time is stored internally rather than read from the clock.
+    /// Has no effect on a clock-backed timer.
+    pub fn tick(&self, delta: Duration) {
+        if !self.driven || self.paused_at.get().is_some() {
+            return;
+        }
+        let before = self.ticked.get();
+        let after = before + delta;
+        self.ticked.set(after);
+        self.just_finished
+            .set(before < self.duration && after >= self.duration);
+    }
+
+    /// Returns `true` only on the [`tick`](Timer::tick) where the accumulated
+    /// time first crossed `duration`.
+    ///
+    /// Subsequent ticks return `false` until the accumulated time drops below
+    /// `duration` again, either through `reset` or through `poll` consuming
+    /// whole intervals of a repeating timer.
+    pub fn just_finished(&self) -> bool {
+        self.just_finished.get()
+    }
+
+    /// Poll the timer, returning how many full intervals have elapsed since
+    /// the last `reset`/`poll`.
+    ///
+    /// Modeled on crosvm's `TimerFd::wait`: rather than a simple `bool`, this
+    /// reports `elapsed / duration` so a control loop running slower than the
+    /// tick rate can detect and compensate for dropped ticks. The counted
+    /// `times_expired * duration` is taken off the timer's elapsed time (it is
+    /// not reset to zero), so the leftover carries over and periodic timers
+    /// don't drift. For a [`Once`] timer the count saturates at 1 and no time
+    /// is consumed: once expired, it reports 1 on every poll until `reset`.
+    ///
+    /// Returns 0 when the timer hasn't expired yet, or when its `duration`
+    /// is zero.
+    ///
+    /// [`Once`]: TimerMode::Once
+    pub fn poll(&self) -> u64 {
+        if self.duration.is_zero() {
+            return 0;
+        }
+
+        let elapsed = self.elapsed();
+
+        if let TimerMode::Once = self.mode {
+            // A one-shot timer must stay expired, so never consume time — just
+            // report whether it has crossed its single deadline.
+            return (elapsed >= self.duration) as u64;
+        }
+
+        let times_expired = (elapsed.as_nanos() / self.duration.as_nanos()) as u64;
+
+        if times_expired > 0 {
+            let advance = self.duration * times_expired as u32;
+            if self.driven {
+                // A driven timer banks time in `ticked`, so consume it there;
+                // advancing `instant` would be ignored by `elapsed()`.
+                self.ticked.set(self.ticked.get() - advance);
+            } else {
+                // Take banked (paused) time first, then the running span, so
+                // time counted here is not counted again by a later poll.
+                let banked = self.accumulated.get();
+                let from_banked = banked.min(advance);
+                self.accumulated.set(banked - from_banked);
+                self.instant.set(self.instant.get() + (advance - from_banked));
+            }
+        }
+
+        times_expired
     }
 
     /// Return a `Duration` of the configured time of the Timer
@@ -90,21 +289,80 @@ impl Timer {
         self.duration
     }
 
-    /// Block execution until the timer expires. 
+    /// Get the `Duration` remaining before the timer expires.
+    ///
+    /// Saturates at zero once the timer is expired.
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.elapsed())
+    }
+
+    /// Fraction of the timer that has elapsed, clamped to `[0.0, 1.0]`.
+    ///
+    /// A zero-`duration` timer is treated as fully elapsed (`1.0`) to avoid a
+    /// divide-by-zero.
+    pub fn percent(&self) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.elapsed().as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0)
+    }
+
+    /// Fraction of the timer still remaining, clamped to `[0.0, 1.0]`.
+    ///
+    /// This is `1.0 - percent()`; a zero-`duration` timer reports `0.0`.
+    pub fn percent_left(&self) -> f32 {
+        1.0 - self.percent()
+    }
+
+    /// Block execution until the timer expires.
     /// 
     /// - If the timer is already expired, this returns immediately
+    /// - This sleeps on the wall clock for the remaining time; a driven or
+    ///   paused timer does not advance meanwhile, so it can still be unexpired
+    ///   on return
     pub fn wait(&self) {
-        if let Some(duration) = self.duration.checked_sub(self.instant.get().elapsed()) {
+        if let Some(duration) = self.duration.checked_sub(self.elapsed()) {
             std::thread::sleep(duration);
         }
     }
 
+    /// Block until the timer expires or `max` elapses, whichever comes first.
+    ///
+    /// Mirrors crosvm's bounded wait: returns [`WaitResult::Expired`] if the
+    /// timer finished within the bound (including when it was already expired),
+    /// or [`WaitResult::Timeout`] if `max` elapsed first. Unlike [`wait`], this
+    /// never stalls longer than `max`, which is useful for a timer armed with a
+    /// very long `duration`.
+    ///
+    /// The sleep is on the wall clock: a driven or paused timer does not advance
+    /// meanwhile, so `Expired` then means the remaining time was slept, not that
+    /// `expired()` is now true.
+    ///
+    /// [`wait`]: Timer::wait
+    pub fn wait_timeout(&self, max: Duration) -> WaitResult {
+        match self.duration.checked_sub(self.elapsed()) {
+            None => WaitResult::Expired,
+            Some(remaining) => {
+                std::thread::sleep(remaining.min(max));
+                if remaining <= max {
+                    WaitResult::Expired
+                } else {
+                    WaitResult::Timeout
+                }
+            }
+        }
+    }
+
     /// Get `Duration` of time elapsed since `Timer` `reset`
     /// 
     /// # Note
     /// A newly constructed timer is considered to be `reset`
     pub fn elapsed(&self) -> Duration {
-        self.instant.get().elapsed()
+        if self.driven {
+            self.ticked.get()
+        } else {
+            self.accumulated.get() + self.running_span()
+        }
     }
 }
 
@@ -135,4 +393,149 @@ mod tests {
         assert!(diff >= 25);
     }
 
+    #[test]
+    fn driven_timer_expires_once_accumulated_time_reaches_duration() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        assert!(!timer.expired());
+
+        timer.tick(Duration::from_millis(60));
+        assert!(!timer.expired());
+
+        timer.tick(Duration::from_millis(60));
+        assert!(timer.expired());
+        assert_eq!(timer.elapsed(), Duration::from_millis(120));
+    }
+
+    #[test]
+    fn driven_just_finished_fires_only_on_the_crossing_tick() {
+        let timer = Timer::driven(Duration::from_millis(100));
+
+        timer.tick(Duration::from_millis(50));
+        assert!(!timer.just_finished());
+
+        timer.tick(Duration::from_millis(50));
+        assert!(timer.just_finished());
+
+        timer.tick(Duration::from_millis(50));
+        assert!(!timer.just_finished());
+    }
+
+    #[test]
+    fn reset_clears_driven_accumulation() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        timer.tick(Duration::from_millis(150));
+        assert!(timer.expired());
+
+        timer.reset();
+        assert!(!timer.expired());
+        assert_eq!(timer.elapsed(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn progress_queries_track_elapsed_fraction() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        timer.tick(Duration::from_millis(25));
+
+        assert_eq!(timer.remaining(), Duration::from_millis(75));
+        assert!((timer.percent() - 0.25).abs() < f32::EPSILON);
+        assert!((timer.percent_left() - 0.75).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn progress_queries_clamp_once_expired() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        timer.tick(Duration::from_millis(250));
+
+        assert_eq!(timer.remaining(), Duration::from_secs(0));
+        assert_eq!(timer.percent(), 1.0);
+        assert_eq!(timer.percent_left(), 0.0);
+    }
+
+    #[test]
+    fn pause_freezes_elapsed_and_resume_continues() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        timer.tick(Duration::from_millis(30));
+
+        timer.pause();
+        assert!(timer.is_paused());
+
+        // Ticks while paused are ignored.
+        timer.tick(Duration::from_millis(1000));
+        assert_eq!(timer.elapsed(), Duration::from_millis(30));
+        assert!(!timer.expired());
+
+        timer.resume();
+        assert!(!timer.is_paused());
+
+        timer.tick(Duration::from_millis(80));
+        assert_eq!(timer.elapsed(), Duration::from_millis(110));
+        assert!(timer.expired());
+    }
+
+    #[test]
+    fn wait_timeout_returns_expired_immediately_when_already_expired() {
+        let timer = Timer::driven(Duration::from_millis(100));
+        timer.tick(Duration::from_millis(150));
+        assert_eq!(timer.wait_timeout(Duration::from_secs(10)), WaitResult::Expired);
+    }
+
+    #[test]
+    #[ignore]
+    fn wait_timeout_times_out_before_a_long_timer_expires() {
+        let timer = Timer::with_duration(Duration::from_secs(3600));
+        assert_eq!(timer.wait_timeout(Duration::from_millis(20)), WaitResult::Timeout);
+    }
+
+    #[test]
+    fn repeating_poll_counts_missed_intervals_without_drift() {
+        let timer = Timer::driven(Duration::from_millis(10));
+        let timer = Timer { mode: TimerMode::Repeating, ..timer };
+
+        // Fall three full intervals (plus a little) behind.
+        timer.tick(Duration::from_millis(35));
+        assert_eq!(timer.poll(), 3);
+
+        // The leftover 5ms is kept, so an immediate re-poll reports nothing new.
+        assert_eq!(timer.poll(), 0);
+        assert_eq!(timer.elapsed(), Duration::from_millis(5));
+    }
+
+    #[test]
+    fn once_poll_leaves_the_timer_expired() {
+        let timer = Timer::driven(Duration::from_millis(10));
+        // Land in [duration, 2*duration): a single `poll()` must not un-expire it.
+        timer.tick(Duration::from_millis(15));
+        assert!(timer.expired());
+
+        assert_eq!(timer.poll(), 1);
+        assert!(timer.expired());
+        assert_eq!(timer.poll(), 1);
+    }
+
+    // Relies on real time like the other `wait`/sleep tests, so it is ignored by
+    // default; run with `cargo test -- --ignored` to exercise it.
+    #[test]
+    #[ignore]
+    fn repeating_poll_counts_missed_intervals_against_the_clock() {
+        let timer = Timer::with_interval(Duration::from_millis(20));
+        std::thread::sleep(Duration::from_millis(65));
+        assert_eq!(timer.poll(), 3);
+        assert_eq!(timer.poll(), 0);
+    }
+
+    #[test]
+    fn at_a_past_deadline_is_already_expired() {
+        let deadline = Instant::now() - Duration::from_secs(1);
+        let timer = Timer::at(deadline);
+        assert!(timer.expired());
+        assert_eq!(timer.remaining(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn at_a_future_deadline_is_not_yet_expired() {
+        let timer = Timer::at(Instant::now() + Duration::from_secs(3600));
+        assert!(!timer.expired());
+        assert!(timer.remaining() > Duration::from_secs(3500));
+    }
+
 }